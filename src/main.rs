@@ -1,14 +1,17 @@
 use std::process;
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::path::PathBuf;
 use std::io::{Write, BufWriter};
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use chrono::Local;
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{info, error};
-use rand::random_range;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rayon::prelude::*;
 
 #[derive(Parser, Debug)]
@@ -33,6 +36,11 @@ struct Args {
     #[arg(long, default_value_t = 50.0)]
     bias: f64,
 
+    /// Seed for reproducible runs (per-trial RNGs are derived from this, so results are
+    /// identical regardless of `--jobs`). A random seed is chosen and logged if omitted.
+    #[arg(long)]
+    seed: Option<u64>,
+
     /// Show colored terminal summary
     #[arg(long, default_value_t = false)]
     color: bool,
@@ -44,32 +52,324 @@ struct Args {
     /// Suppress terminal summary output
     #[arg(long, default_value_t = false)]
     quiet: bool,
+
+    /// Run a bias sweep instead of a single simulation, aggregating results into a Markdown
+    /// table (see `--bias-range` and `--seeds`).
+    #[arg(long, default_value_t = false)]
+    sweep: bool,
+
+    /// Bias values to sweep as `start:end:step` percentages, e.g. `10:90:10`
+    #[arg(long, default_value = "10:90:10")]
+    bias_range: String,
+
+    /// Seed range to sweep per bias cell as `start:end` (inclusive), e.g. `0:9999`
+    #[arg(long, default_value = "0:999")]
+    seeds: String,
+
+    /// Output format: `csv`/`jsonl` stream per-trial records, `json` emits an aggregated
+    /// JSON summary instead of the plain-text report
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Csv,
+    Jsonl,
+    Json,
+}
+
+/// One row of a bias sweep: the configured bias and its aggregated stats across the seed range.
+struct SweepRow {
+    bias: f64,
+    mean_pct: f64,
+    stdev: f64,
+    hit_rate: f64,
+}
+
+fn parse_bias_range(spec: &str) -> Result<(f64, f64, f64), String> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    if parts.len() != 3 {
+        return Err(format!("expected `start:end:step`, got `{}`", spec));
+    }
+    let start: f64 = parts[0].parse().map_err(|_| format!("invalid bias start `{}`", parts[0]))?;
+    let end: f64 = parts[1].parse().map_err(|_| format!("invalid bias end `{}`", parts[1]))?;
+    let step: f64 = parts[2].parse().map_err(|_| format!("invalid bias step `{}`", parts[2]))?;
+    if step <= 0.0 {
+        return Err("bias step must be positive".to_string());
+    }
+    Ok((start, end, step))
+}
+
+fn parse_seed_range(spec: &str) -> Result<(u64, u64), String> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    if parts.len() != 2 {
+        return Err(format!("expected `start:end`, got `{}`", spec));
+    }
+    let start: u64 = parts[0].parse().map_err(|_| format!("invalid seed start `{}`", parts[0]))?;
+    let end: u64 = parts[1].parse().map_err(|_| format!("invalid seed end `{}`", parts[1]))?;
+    if end < start {
+        return Err("seed range end must be >= start".to_string());
+    }
+    Ok((start, end))
+}
+
+/// A large odd multiplier (from SplitMix64) used to mix the trial index into the seed.
+/// Plain `wrapping_add` would make seed `s`/trial `t` and seed `s+1`/trial `t-1` derive the
+/// exact same RNG state, so adjacent seeds would just be one-trial shifts of each other
+/// instead of independent streams. XOR-ing in the trial index scaled by this constant avoids
+/// that collapse.
+const TRIAL_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Builds the per-trial RNG from the global seed and trial index, independent of execution
+/// order and independent across distinct seeds.
+fn trial_rng(seed: u64, trial: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed ^ trial.wrapping_mul(TRIAL_MIX))
+}
+
+/// Flips `trials` coins for a single `(bias, seed)` cell and returns the resulting head percentage.
+/// Uses the same per-trial RNG derivation as the main simulation loop, so sweep cells are
+/// reproducible the same way a normal `--seed` run is.
+fn simulate_head_pct(trials: u64, bias: f64, seed: u64) -> f64 {
+    let heads: u64 = (1..=trials)
+        .filter(|trial| {
+            let mut rng = trial_rng(seed, *trial);
+            rng.random_range(0..100) < bias.round() as u32
+        })
+        .count() as u64;
+    100.0 * heads as f64 / trials as f64
+}
+
+/// Sweeps `bias_range` across `seed_range`, running `trials` flips per `(bias, seed)` cell, and
+/// aggregates each bias's results into one `SweepRow`.
+fn run_sweep(trials: u64, bias_range: (f64, f64, f64), seed_range: (u64, u64)) -> Vec<SweepRow> {
+    let (bias_start, bias_end, bias_step) = bias_range;
+    let (seed_start, seed_end) = seed_range;
+    let seeds: Vec<u64> = (seed_start..=seed_end).collect();
+
+    let mut rows = Vec::new();
+    let mut bias = bias_start;
+    while bias <= bias_end + 1e-9 {
+        let pcts: Vec<f64> = seeds.iter().map(|&seed| simulate_head_pct(trials, bias, seed)).collect();
+
+        let mean_pct = pcts.iter().sum::<f64>() / pcts.len() as f64;
+        let variance = pcts.iter().map(|p| (p - mean_pct).powi(2)).sum::<f64>() / pcts.len() as f64;
+        let stdev = variance.sqrt();
+        let hits = pcts.iter().filter(|p| (*p - bias).abs() <= 1.0).count();
+        let hit_rate = hits as f64 / pcts.len() as f64;
+
+        rows.push(SweepRow { bias, mean_pct, stdev, hit_rate });
+        bias += bias_step;
+    }
+    rows
+}
+
+fn render_sweep_table(rows: &[SweepRow]) -> String {
+    let mut table = String::new();
+    table.push_str("| bias | mean% | stdev | hit-rate |\n");
+    table.push_str("|------|-------|-------|----------|\n");
+    for row in rows {
+        table.push_str(&format!(
+            "| {:.1} | {:.2} | {:.2} | {:.2} |\n",
+            row.bias, row.mean_pct, row.stdev, row.hit_rate
+        ));
+    }
+    table
+}
+
+/// Fairness diagnostics computed across the whole run, beyond the raw head/tail counts.
+struct Diagnostics {
+    longest_head_streak: u64,
+    longest_tail_streak: u64,
+    run_count: u64,
+    chi_square: f64,
+    biased: bool,
+    runs_z: f64,
+    non_random_ordering: bool,
+}
+
+/// Everything needed to report on a completed run, bundled so the reporting methods don't
+/// have to take the totals and diagnostics as a long, error-prone parameter list.
+struct RunSummary {
+    total: u64,
+    bias: f64,
+    heads: u64,
+    tails: u64,
+    diagnostics: Diagnostics,
+}
+
+/// Replays the trial outcomes in order (cheap: same per-trial RNG derivation as the main
+/// loop) to compute the longest streaks and run count, then derives a chi-square
+/// goodness-of-fit against `bias` and a Wald-Wolfowitz runs z-score from the totals.
+fn compute_diagnostics(trials: u64, bias: f64, seed: u64, heads: u64, tails: u64) -> Diagnostics {
+    let mut longest_head_streak = 0u64;
+    let mut longest_tail_streak = 0u64;
+    let mut current_streak = 0u64;
+    let mut current_is_head: Option<bool> = None;
+    let mut run_count = 0u64;
+
+    for trial in 1..=trials {
+        let mut rng = trial_rng(seed, trial);
+        let is_head = rng.random_range(0..100) < bias.round() as u32;
+
+        if current_is_head == Some(is_head) {
+            current_streak += 1;
+        } else {
+            current_streak = 1;
+            run_count += 1;
+            current_is_head = Some(is_head);
+        }
+
+        if is_head {
+            longest_head_streak = longest_head_streak.max(current_streak);
+        } else {
+            longest_tail_streak = longest_tail_streak.max(current_streak);
+        }
+    }
+
+    let n = trials as f64;
+    let h = heads as f64;
+    let t = tails as f64;
+    let p = (bias / 100.0).clamp(0.0001, 0.9999);
+    let expected_heads = n * p;
+    let expected_tails = n * (1.0 - p);
+    let chi_square = (h - expected_heads).powi(2) / expected_heads + (t - expected_tails).powi(2) / expected_tails;
+    let biased = chi_square > 3.841;
+
+    // Wald-Wolfowitz runs test: is the heads/tails ordering itself random, independent of bias?
+    let runs_mean = 2.0 * h * t / n + 1.0;
+    let runs_variance = (2.0 * h * t * (2.0 * h * t - n)) / (n * n * (n - 1.0));
+    let runs_z = if runs_variance > 0.0 {
+        (run_count as f64 - runs_mean) / runs_variance.sqrt()
+    } else {
+        0.0
+    };
+    let non_random_ordering = runs_z.abs() > 1.96;
+
+    Diagnostics {
+        longest_head_streak,
+        longest_tail_streak,
+        run_count,
+        chi_square,
+        biased,
+        runs_z,
+        non_random_ordering,
+    }
+}
+
+/// Writes one per-trial record at a time, so the simulation loop stays agnostic to the
+/// on-disk format. Implementations own their file handle and are responsible for their
+/// own framing (CSV row vs. JSON object per line).
+trait RecordWriter: Send {
+    fn write_record(&mut self, trial: u64, outcome: &str, value: u32) -> std::io::Result<()>;
+    fn flush(&mut self) -> std::io::Result<()>;
+}
+
+/// A completed trial, as sent from a worker to the dedicated writer thread.
+struct Record {
+    trial: u64,
+    outcome: String,
+    value: u32,
+}
+
+/// How many records the writer thread batches between flushes.
+const WRITER_FLUSH_INTERVAL: usize = 1000;
+
+/// Spawns a thread that owns `writer` exclusively and drains `Record`s from a channel,
+/// batching `writeln!` calls instead of flushing on every record. Workers never touch the
+/// file directly; they just send completed records and keep flipping. Records can arrive
+/// out of trial order (parallel workers finish in whatever order they finish), so the
+/// thread holds them in `pending`, keyed by trial, and only writes the run of trials
+/// starting at `next_trial` once it's complete — this is what makes the output file
+/// identical regardless of `--jobs`. Drop the returned `Sender` to let the channel close
+/// and the thread finish, then join the handle.
+fn spawn_writer_thread(mut writer: Box<dyn RecordWriter>) -> (Sender<Record>, JoinHandle<()>) {
+    let (tx, rx) = mpsc::channel::<Record>();
+    let handle = thread::spawn(move || {
+        let mut pending: BTreeMap<u64, Record> = BTreeMap::new();
+        let mut next_trial = 1u64;
+        let mut written = 0usize;
+
+        let mut write_one = |writer: &mut Box<dyn RecordWriter>, record: Record| {
+            if let Err(e) = writer.write_record(record.trial, &record.outcome, record.value) {
+                error!("Failed to write record: {}", e);
+            }
+            written += 1;
+            if written >= WRITER_FLUSH_INTERVAL {
+                let _ = writer.flush();
+                written = 0;
+            }
+        };
+
+        for record in rx {
+            pending.insert(record.trial, record);
+            while let Some(record) = pending.remove(&next_trial) {
+                next_trial += 1;
+                write_one(&mut writer, record);
+            }
+        }
+
+        if let Err(e) = writer.flush() {
+            error!("Failed to flush output file: {}", e);
+        }
+    });
+    (tx, handle)
+}
+
+struct CsvRecordWriter(BufWriter<File>);
+
+impl CsvRecordWriter {
+    fn new(mut file: BufWriter<File>) -> std::io::Result<Self> {
+        writeln!(file, "Trial,Outcome,RandomValue")?;
+        Ok(Self(file))
+    }
+}
+
+impl RecordWriter for CsvRecordWriter {
+    fn write_record(&mut self, trial: u64, outcome: &str, value: u32) -> std::io::Result<()> {
+        writeln!(self.0, "{},{},{}", trial, outcome, value)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+struct JsonlRecordWriter(BufWriter<File>);
+
+impl RecordWriter for JsonlRecordWriter {
+    fn write_record(&mut self, trial: u64, outcome: &str, value: u32) -> std::io::Result<()> {
+        writeln!(self.0, "{{\"trial\":{},\"outcome\":\"{}\",\"value\":{}}}", trial, outcome, value)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
 }
 
 struct CoinFlipStreamer {
     trials: u64,
-    head_count: Arc<Mutex<u64>>,
-    tail_count: Arc<Mutex<u64>>,
 }
 
 impl CoinFlipStreamer {
     fn new(trials: u64) -> Self {
-        Self {
-            trials,
-            head_count: Arc::new(Mutex::new(0)),
-            tail_count: Arc::new(Mutex::new(0)),
-        }
+        Self { trials }
     }
 
-    fn run_parallel(&self, csv_mutex: Option<Arc<Mutex<BufWriter<File>>>>, jobs: usize, bias: f64) {
-        info!("Starting simulation with {} trials, bias {}%, using {} thread(s)", self.trials, bias, jobs);
+    /// Runs the simulation and returns the `(heads, tails)` totals. The parallel path
+    /// accumulates per-worker totals in plain stack variables via rayon's `fold`/`reduce`
+    /// and only combines them once per chunk, so no lock is taken on the hot per-flip path.
+    /// Each worker keeps its own clone of `sender` for the lifetime of its chunk and ships
+    /// completed records to the dedicated writer thread instead of touching a file directly.
+    fn run_parallel(&self, sender: Option<Sender<Record>>, jobs: usize, bias: f64, seed: u64) -> (u64, u64) {
+        info!("Starting simulation with {} trials, bias {}%, using {} thread(s), seed {}", self.trials, bias, jobs, seed);
 
         let pb = ProgressBar::new(self.trials);
         pb.set_style(ProgressStyle::with_template("[{elapsed_precise}] [{wide_bar:.cyan/blue}] {percent}% {msg}")
             .unwrap()
             .progress_chars("=>-"));
 
-        if jobs > 1 {
+        let (heads, tails) = if jobs > 1 {
             rayon::ThreadPoolBuilder::new()
                 .num_threads(jobs)
                 .build_global()
@@ -78,55 +378,70 @@ impl CoinFlipStreamer {
                     process::exit(1);
                 });
 
-            (1..=self.trials).into_par_iter().for_each(|trial| {
-                self.process_trial(trial, bias, &csv_mutex, &pb);
-            });
+            (1..=self.trials)
+                .into_par_iter()
+                .fold(
+                    || (0u64, 0u64, sender.clone()),
+                    |(heads, tails, local_sender), trial| {
+                        let is_head = self.process_trial(trial, bias, seed, &local_sender, &pb);
+                        if is_head {
+                            (heads + 1, tails, local_sender)
+                        } else {
+                            (heads, tails + 1, local_sender)
+                        }
+                    },
+                )
+                .map(|(heads, tails, _)| (heads, tails))
+                .reduce(|| (0u64, 0u64), |a, b| (a.0 + b.0, a.1 + b.1))
         } else {
+            let mut heads = 0u64;
+            let mut tails = 0u64;
             for trial in 1..=self.trials {
-                self.process_trial(trial, bias, &csv_mutex, &pb);
+                if self.process_trial(trial, bias, seed, &sender, &pb) {
+                    heads += 1;
+                } else {
+                    tails += 1;
+                }
             }
-        }
+            (heads, tails)
+        };
 
         pb.finish_with_message("Done");
         info!("Simulation complete");
+        (heads, tails)
     }
 
+    /// Flips one trial and returns `true` if it landed heads.
     fn process_trial(
         &self,
         trial: u64,
         bias: f64,
-        csv_mutex: &Option<Arc<Mutex<BufWriter<File>>>>,
+        seed: u64,
+        sender: &Option<Sender<Record>>,
         pb: &ProgressBar,
-    ) {
-        let value = random_range(0..100);
+    ) -> bool {
+        // Each trial gets its own RNG derived from the global seed and trial index, so the
+        // outcome never depends on which thread happens to process it or in what order.
+        let mut rng = trial_rng(seed, trial);
+        let value = rng.random_range(0..100);
         let (outcome_str, is_head) = if value < bias.round() as u32 {
             ("H".to_string(), true)
         } else {
             ("T".to_string(), false)
         };
 
-        if let Some(file_mutex) = csv_mutex {
-            if let Ok(mut file) = file_mutex.lock() {
-                let _ = writeln!(file, "{},{},{}", trial, outcome_str, value);
-            }
-        }
-
-        if is_head {
-            let mut heads = self.head_count.lock().unwrap();
-            *heads += 1;
-        } else {
-            let mut tails = self.tail_count.lock().unwrap();
-            *tails += 1;
+        if let Some(sender) = sender {
+            let _ = sender.send(Record { trial, outcome: outcome_str, value });
         }
 
         pb.inc(1);
+        is_head
     }
 
-    fn export_summary(&self, filename: &str, total: u64, bias: f64) -> Result<(), Box<dyn std::error::Error>> {
-        let heads = *self.head_count.lock().unwrap();
-        let tails = *self.tail_count.lock().unwrap();
-        let head_pct = 100.0 * heads as f64 / total as f64;
-        let tail_pct = 100.0 * tails as f64 / total as f64;
+    fn export_summary(&self, filename: &str, summary: &RunSummary) -> Result<(), Box<dyn std::error::Error>> {
+        let RunSummary { total, bias, heads, tails, diagnostics } = summary;
+        let head_pct = 100.0 * *heads as f64 / *total as f64;
+        let tail_pct = 100.0 * *tails as f64 / *total as f64;
 
         info!("Writing summary to {}", filename);
         let mut file = File::create(filename)?;
@@ -136,15 +451,48 @@ impl CoinFlipStreamer {
         writeln!(file, "Bias: {:.2}%", bias)?;
         writeln!(file, "Heads: {} ({:.2}%)", heads, head_pct)?;
         writeln!(file, "Tails: {} ({:.2}%)", tails, tail_pct)?;
+        writeln!(file, "Longest Heads Streak: {}", diagnostics.longest_head_streak)?;
+        writeln!(file, "Longest Tails Streak: {}", diagnostics.longest_tail_streak)?;
+        writeln!(file, "Runs: {}", diagnostics.run_count)?;
+        writeln!(
+            file,
+            "Chi-square: {:.4} ({})",
+            diagnostics.chi_square,
+            if diagnostics.biased { "biased (reject fairness at \u{3b1}=0.05)" } else { "consistent with configured bias" }
+        )?;
+        writeln!(
+            file,
+            "Runs z-score: {:.4} ({})",
+            diagnostics.runs_z,
+            if diagnostics.non_random_ordering { "non-random ordering" } else { "consistent with random ordering" }
+        )?;
         info!("Summary report written.");
         Ok(())
     }
 
-    fn print_summary_to_terminal(&self, total: u64, bias: f64, color: bool, chart: bool) {
-        let heads = *self.head_count.lock().unwrap();
-        let tails = *self.tail_count.lock().unwrap();
-        let head_pct = 100.0 * heads as f64 / total as f64;
-        let tail_pct = 100.0 * tails as f64 / total as f64;
+    fn export_summary_json(&self, filename: &str, summary: &RunSummary) -> Result<(), Box<dyn std::error::Error>> {
+        let RunSummary { total, bias, heads, tails, diagnostics } = summary;
+        let head_pct = 100.0 * *heads as f64 / *total as f64;
+
+        info!("Writing JSON summary to {}", filename);
+        let mut file = File::create(filename)?;
+        writeln!(
+            file,
+            "{{\"trials\":{},\"bias\":{},\"heads\":{},\"tails\":{},\"head_pct\":{:.4},\
+             \"longest_head_streak\":{},\"longest_tail_streak\":{},\"runs\":{},\
+             \"chi_square\":{:.4},\"biased\":{},\"runs_z\":{:.4},\"non_random_ordering\":{}}}",
+            total, bias, heads, tails, head_pct,
+            diagnostics.longest_head_streak, diagnostics.longest_tail_streak, diagnostics.run_count,
+            diagnostics.chi_square, diagnostics.biased, diagnostics.runs_z, diagnostics.non_random_ordering
+        )?;
+        info!("JSON summary written.");
+        Ok(())
+    }
+
+    fn print_summary_to_terminal(&self, summary: &RunSummary, color: bool, chart: bool) {
+        let RunSummary { total, bias, heads, tails, diagnostics } = summary;
+        let head_pct = 100.0 * *heads as f64 / *total as f64;
+        let tail_pct = 100.0 * *tails as f64 / *total as f64;
 
         let (h_label, t_label) = if color {
             (
@@ -164,6 +512,19 @@ impl CoinFlipStreamer {
         println!("Bias: {:.2}%", bias);
         println!("{}", h_label);
         println!("{}", t_label);
+        println!("Longest Heads Streak: {}", diagnostics.longest_head_streak);
+        println!("Longest Tails Streak: {}", diagnostics.longest_tail_streak);
+        println!("Runs: {}", diagnostics.run_count);
+        println!(
+            "Chi-square: {:.4} ({})",
+            diagnostics.chi_square,
+            if diagnostics.biased { "biased (reject fairness at \u{3b1}=0.05)" } else { "consistent with configured bias" }
+        );
+        println!(
+            "Runs z-score: {:.4} ({})",
+            diagnostics.runs_z,
+            if diagnostics.non_random_ordering { "non-random ordering" } else { "consistent with random ordering" }
+        );
 
         if chart {
             let bar_len = 40;
@@ -205,60 +566,113 @@ fn main() {
         }
     }
 
+    if args.sweep {
+        let bias_range = parse_bias_range(&args.bias_range).unwrap_or_else(|e| {
+            eprintln!("Invalid --bias-range: {}", e);
+            process::exit(1);
+        });
+        let seed_range = parse_seed_range(&args.seeds).unwrap_or_else(|e| {
+            eprintln!("Invalid --seeds: {}", e);
+            process::exit(1);
+        });
+
+        info!("Starting bias sweep: bias-range {:?}, seeds {:?}, {} trials per cell", bias_range, seed_range, args.trials);
+        let rows = run_sweep(args.trials, bias_range, seed_range);
+        let table = render_sweep_table(&rows);
+
+        print!("\n{}", table);
+
+        let results_path = output_dir.join("results.md");
+        if let Err(e) = fs::write(&results_path, &table) {
+            eprintln!("Failed to write sweep results: {}", e);
+            process::exit(1);
+        }
+        println!("\nSweep results written to {}", results_path.display());
+        return;
+    }
+
     let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
-    let csv_filename_raw = args.output.unwrap_or_else(|| format!("coinflip_{}.csv", timestamp));
-    let csv_filename = sanitize_filename(&csv_filename_raw);
-    let summary_filename = format!("summary_{}.txt", timestamp);
+    let record_ext = match args.format {
+        OutputFormat::Csv => "csv",
+        OutputFormat::Jsonl => "jsonl",
+        OutputFormat::Json => "csv",
+    };
+    let record_filename_raw = args.output.unwrap_or_else(|| format!("coinflip_{}.{}", timestamp, record_ext));
+    let record_filename = sanitize_filename(&record_filename_raw);
+    let summary_ext = if args.format == OutputFormat::Json { "json" } else { "txt" };
+    let summary_filename = format!("summary_{}.{}", timestamp, summary_ext);
 
-    let csv_path = output_dir.join(&csv_filename);
+    let record_path = output_dir.join(&record_filename);
     let summary_path = output_dir.join(&summary_filename);
 
-    let csv_mutex = if !args.no_csv {
-        match File::create(&csv_path) {
+    let (record_sender, writer_handle) = if !args.no_csv {
+        match File::create(&record_path) {
             Ok(file) => {
-                let mut writer = BufWriter::new(file);
-                if let Err(e) = writeln!(writer, "Trial,Outcome,RandomValue") {
-                    eprintln!("Failed to write CSV header: {}", e);
-                    process::exit(1);
-                }
-                Some(Arc::new(Mutex::new(writer)))
+                let buffered = BufWriter::new(file);
+                let writer: Box<dyn RecordWriter> = match args.format {
+                    OutputFormat::Csv | OutputFormat::Json => match CsvRecordWriter::new(buffered) {
+                        Ok(w) => Box::new(w),
+                        Err(e) => {
+                            eprintln!("Failed to write CSV header: {}", e);
+                            process::exit(1);
+                        }
+                    },
+                    OutputFormat::Jsonl => Box::new(JsonlRecordWriter(buffered)),
+                };
+                let (tx, handle) = spawn_writer_thread(writer);
+                (Some(tx), Some(handle))
             }
             Err(e) => {
-                eprintln!("Failed to create CSV file: {}", e);
+                eprintln!("Failed to create output file: {}", e);
                 process::exit(1);
             }
         }
     } else {
-        None
+        (None, None)
     };
 
+    let seed = args.seed.unwrap_or_else(|| {
+        let random_seed = rand::random::<u64>();
+        info!("No --seed given, using random seed {} (pass --seed {} to replay this run)", random_seed, random_seed);
+        random_seed
+    });
+
     let sim = CoinFlipStreamer::new(args.trials);
-    sim.run_parallel(csv_mutex.clone(), args.jobs, args.bias);
+    let (heads, tails) = sim.run_parallel(record_sender, args.jobs, args.bias, seed);
+    let diagnostics = compute_diagnostics(args.trials, args.bias, seed, heads, tails);
+    let summary = RunSummary { total: args.trials, bias: args.bias, heads, tails, diagnostics };
 
-    if let Err(e) = sim.export_summary(summary_path.to_str().unwrap(), args.trials, args.bias) {
+    let summary_result = if args.format == OutputFormat::Json {
+        sim.export_summary_json(summary_path.to_str().unwrap(), &summary)
+    } else {
+        sim.export_summary(summary_path.to_str().unwrap(), &summary)
+    };
+    if let Err(e) = summary_result {
         eprintln!("Failed to write summary file: {}", e);
         process::exit(1);
     }
 
-    if let Some(writer) = csv_mutex {
-        let mut writer = writer.lock().unwrap();
-        if let Err(e) = writer.flush() {
-            eprintln!("Failed to flush CSV: {}", e);
+    // run_parallel took ownership of our sender and dropped it, along with its worker clones,
+    // when it returned. That closed the channel, so the writer thread's receive loop ends and
+    // it flushes on its own exit.
+    if let Some(handle) = writer_handle {
+        if handle.join().is_err() {
+            eprintln!("Writer thread panicked");
             process::exit(1);
         }
     }
 
     if !args.quiet {
-        sim.print_summary_to_terminal(args.trials, args.bias, args.color, args.chart);
+        sim.print_summary_to_terminal(&summary, args.color, args.chart);
     }
 
     println!(
         "\nDone! Summary written to {}\n{}",
         summary_path.display(),
         if !args.no_csv {
-            format!("CSV written to {}", csv_path.display())
+            format!("Records written to {}", record_path.display())
         } else {
-            "CSV disabled (--no-csv)".to_string()
+            "Per-trial records disabled (--no-csv)".to_string()
         }
     );
 }